@@ -195,6 +195,132 @@ class Client(Dispatcher, EventTemplateMixin):
     max_heartbeat_timeout: Optional[int]
         The maximum timeout in seconds between sending a heartbeat to the server.
         If heartbeat took longer than this timeout, the client will attempt to reconnect.
+
+    user_agent: Optional[str]
+        The User-Agent header sent with every request to the REST API.
+        Defaults to one identifying this library and its version.
+
+    base_url: Optional[str]
+        The base URL of the REST API, for example that of a self-hosted instance.
+        Defaults to ``https://api.ferris.chat/v0``.
+
+    connector: Optional[:class:`aiohttp.BaseConnector`]
+        The connector to send requests to the REST API through, for example
+        one backed by a mock server in tests. This replaces the connection pool,
+        so ``ssl``, ``local_address`` and the connection limits are ignored.
+
+    trace_configs: Optional[List[:class:`aiohttp.TraceConfig`]]
+        Hooks called as requests to the REST API are made, which can be used
+        to record or assert on the exact requests sent.
+
+    max_tries: Optional[int]
+        The maximum number of times a request to the REST API is attempted
+        before giving up. Defaults to ``3``. A request that timed out or lost
+        its connection is only sent again if its method is idempotent or it has
+        an ``Idempotency-Key`` header, since it may already have been processed.
+
+    timeout: Optional[float]
+        The maximum number of seconds a request to the REST API may take.
+        Pass ``None`` to disable the timeout. Defaults to ``300``.
+
+    connect_timeout: Optional[float]
+        The maximum number of seconds connecting to the REST API may take,
+        including the TLS handshake. Defaults to no limit other than ``timeout``.
+
+    backoff: Optional[float]
+        The number of seconds to wait before retrying a request that failed with
+        a server error. This is doubled after every attempt. Defaults to ``0.5``.
+
+    jitter: Optional[bool]
+        Whether to wait a random duration between ``0`` and the backoff instead,
+        so that many clients do not retry at the same time. Defaults to ``False``.
+
+    retry_statuses: Optional[Iterable[int]]
+        The status codes a request is retried on. By default, requests are
+        retried when ratelimited (``429``) or on any server error (``5xx``).
+
+    ssl: Optional[:class:`ssl.SSLContext`]
+        The SSL context used to verify the server's certificate.
+        Defaults to one trusting the system's certificates.
+
+    danger_accept_invalid_certs: Optional[bool]
+        Whether to accept any certificate, disabling verification entirely.
+        This makes connections open to interception and should never be done
+        in production. Defaults to ``True`` only if the ``FERRIS_USE_SSL``
+        environment variable is ``false``.
+
+    ca_data: Optional[str]
+        PEM encoded certificates to trust in addition to the system ones,
+        for example those of a self-hosted instance's internal CA.
+
+    connection_limit: Optional[int]
+        The maximum number of simultaneous connections to the REST API.
+        ``0`` means no limit. Defaults to ``100``.
+
+    connection_limit_per_host: Optional[int]
+        The maximum number of simultaneous connections to a single host.
+        ``0`` means no limit. Defaults to ``0``.
+
+    keepalive_timeout: Optional[float]
+        The number of seconds an idle connection is kept open for reuse.
+        Defaults to ``15``.
+
+    local_address: Optional[str]
+        The local IP address to send requests to the REST API from.
+
+    proxy: Optional[str]
+        The URL of an HTTP proxy to send every request through.
+
+    proxy_auth: Optional[Tuple[str, str]]
+        The login and password used to authenticate with the proxy.
+
+    circuit_breaker_threshold: Optional[int]
+        If passed, requests to a host are rejected with :exc:`CircuitBreakerOpen`
+        once this many requests to it failed in a row, until the cooldown is over.
+
+    circuit_breaker_cooldown: Optional[float]
+        The number of seconds requests to a failing host are rejected for.
+        Defaults to ``30``.
+
+    requests_per_second: Optional[float]
+        If passed, requests to the REST API are spaced out so that no more
+        than this many are sent per second, across all concurrent tasks.
+
+    follow_redirects: Optional[bool]
+        Whether to follow redirects returned by the REST API. Defaults to ``True``.
+
+    max_redirects: Optional[int]
+        The maximum number of redirects to follow for a single request.
+        Defaults to ``10``.
+
+    max_response_size: Optional[int]
+        The maximum size in bytes of a response body from the REST API.
+        Larger responses raise :exc:`ResponseTooLarge` instead of being read.
+
+    idempotency_keys: Optional[bool]
+        Whether to send a generated ``Idempotency-Key`` header with ``POST`` and
+        ``PATCH`` requests, kept the same across retries so the REST API can
+        discard duplicates. Defaults to ``False``.
+
+    etag_cache: Optional[bool]
+        Whether to keep successful ``GET`` responses carrying an ``ETag`` or
+        ``Last-Modified`` header in memory, and send their validators when
+        requesting the same URL with the same token again. A ``304 Not Modified``
+        response then returns the cached response instead. Requests passing
+        ``params`` are never cached. Defaults to ``False``.
+
+    etag_cache_size: Optional[int]
+        The maximum number of responses kept by ``etag_cache``, evicting the
+        least recently used one first. Defaults to ``256``.
+
+    cookies: Optional[bool]
+        Whether to store cookies set by the REST API and send them back on
+        subsequent requests. Defaults to ``True``.
+
+    auto_decompress: Optional[bool]
+        Whether to transparently decompress gzip and deflate encoded responses.
+        Brotli is also supported when installed with the ``performance`` extra.
+        Defaults to ``True``.
     """
 
     def __init__(
@@ -602,8 +728,7 @@ class Client(Dispatcher, EventTemplateMixin):
             await self.ws.close(code=1000)
 
         if http := getattr(self._connection, '_http', None):
-            if session := getattr(http, 'session', None):
-                await session.close()
+            await http.close()
 
         self.dispatch('close')
 
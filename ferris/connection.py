@@ -41,6 +41,8 @@ class Connection:
 
         self._max_heartbeat_timeout: int = options.get("max_heartbeat_timeout", 60)
 
+        self._options: Dict[str, Any] = options
+
         self.clear_store()
 
     @property
@@ -59,12 +61,12 @@ class Connection:
         self.__token = token
 
     def _initialize_http(self, token: str, /) -> None:
-        self._http: HTTPClient = HTTPClient(token)
+        self._http: HTTPClient = HTTPClient(token, **self._options)
         self._store_token(token)
 
     async def _initialize_http_with_email(self, email: str, password: str, /) -> None:
         self._http: HTTPClient = await HTTPClient.from_email_and_password(
-            email, password
+            email, password, **self._options
         )
         self._store_token(self._http.token)
 
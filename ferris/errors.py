@@ -11,8 +11,13 @@ __all__ = (
     'NotFound',
     'FerrisServerError',
     'FerrisUnavailable',
+    'RequestError',
+    'RequestTimeout',
+    'ConnectionFailure',
+    'CircuitBreakerOpen',
     'WebsocketException',
     'MissingImplementation',
+    'ResponseTooLarge',
     'Reconnect',
 )
 
@@ -33,13 +38,25 @@ class HTTPException(FerrisException):
     ----------
     status: int
         The status code of the response.
+    url: str
+        The URL of the request that failed.
+    body: Optional[str]
+        The raw body of the response, if it was read.
     resp: :class:`aiohttp.ClientResponse`
         The aiohttp response object.
     """
 
-    def __init__(self, resp: ClientResponse, content: Optional[str] = None):
+    def __init__(
+        self,
+        resp: ClientResponse,
+        content: Optional[str] = None,
+        *,
+        body: Optional[str] = None,
+    ):
         content = content or resp.reason
         self.status = resp.status
+        self.url = str(resp.url)
+        self.body = body
         self.resp = resp
         super().__init__(content)
 
@@ -90,6 +107,59 @@ class FerrisUnavailable(HTTPException):
     pass
 
 
+class ResponseTooLarge(HTTPException):
+    """The response body was larger than the configured ``max_response_size``."""
+
+    pass
+
+
+class RequestError(FerrisException):
+    """
+    Raised when a request could not be completed without getting a response.
+    This is the base class of :exc:`RequestTimeout` and :exc:`ConnectionFailure`.
+
+    Attributes
+    ----------
+    original: Exception
+        The exception that caused this error.
+    """
+
+    def __init__(self, original: Exception):
+        self.original = original
+        super().__init__(str(original) or original.__class__.__name__)
+
+
+class RequestTimeout(RequestError):
+    """The request took longer than the configured timeout."""
+
+    pass
+
+
+class ConnectionFailure(RequestError):
+    """
+    The connection to the server failed, for example because it was refused,
+    the host could not be resolved or the server disconnected.
+    """
+
+    pass
+
+
+class CircuitBreakerOpen(FerrisException):
+    """
+    Raised instead of sending a request to a host that kept failing recently.
+    Requests are let through again once the cooldown is over.
+
+    Attributes
+    ----------
+    host: str
+        The host requests are currently not being sent to.
+    """
+
+    def __init__(self, host: str):
+        self.host = host
+        super().__init__(f'Not sending requests to {host} until it recovers')
+
+
 class WebsocketException(FerrisException):
     """Base class for all websocket exceptions."""
 
@@ -1,27 +1,63 @@
 from __future__ import annotations
 
 import asyncio
+import codecs
+import copy
+import ipaddress
 import logging
 import os
-from typing import TYPE_CHECKING, Awaitable, ClassVar, Dict, Optional
-from urllib.parse import quote
+import random
+import re
+import ssl
+import tempfile
+import time
+import uuid
+from collections import OrderedDict
+from contextlib import contextmanager, suppress
+from datetime import datetime, timezone
+from email.utils import parsedate_to_datetime
+from typing import (
+    TYPE_CHECKING,
+    Any,
+    AsyncContextManager,
+    AsyncIterator,
+    Awaitable,
+    ClassVar,
+    Dict,
+    FrozenSet,
+    Iterable,
+    Iterator,
+    List,
+    Optional,
+    Set,
+    Tuple,
+    Union,
+)
+from urllib.parse import quote, urlsplit
 
 import aiohttp
 
 from . import __version__
 from .errors import (
     BadRequest,
+    CircuitBreakerOpen,
+    ConnectionFailure,
     FerrisServerError,
     FerrisUnavailable,
     Forbidden,
     HTTPException,
     MissingImplementation,
     NotFound,
+    RequestError,
+    RequestTimeout,
+    ResponseTooLarge,
     Unauthorized,
 )
 from .utils import from_json
 
 if TYPE_CHECKING:
+    from multidict import CIMultiDictProxy, MultiDictProxy
+
     from .types import Data, SupportsStr
 
 log = logging.getLogger(__name__)
@@ -29,9 +65,411 @@ log = logging.getLogger(__name__)
 __all__ = (
     'APIRouter',
     'HTTPClient',
+    'HTTPResponse',
+)
+
+# https://datatracker.ietf.org/doc/html/rfc7230#section-3.2.6
+TOKEN_REGEX: re.Pattern = re.compile(r"[!#$%&'*+\-.^_`|~0-9A-Za-z]+")
+
+# https://datatracker.ietf.org/doc/html/rfc7231#section-4.2.2
+IDEMPOTENT_METHODS: FrozenSet[str] = frozenset(
+    ('GET', 'HEAD', 'PUT', 'DELETE', 'OPTIONS')
 )
 
 
+@contextmanager
+def _translate_errors() -> Iterator[None]:
+    try:
+        yield
+    except asyncio.TimeoutError as exc:
+        raise RequestTimeout(exc) from exc
+    except aiohttp.ClientConnectionError as exc:
+        raise ConnectionFailure(exc) from exc
+    except aiohttp.ClientError as exc:
+        raise RequestError(exc) from exc
+
+
+async def _read_response(
+    request: AsyncContextManager[aiohttp.ClientResponse],
+    /,
+    *,
+    read_body: bool = True,
+    max_size: Optional[int] = None,
+) -> Tuple[aiohttp.ClientResponse, bytes]:
+    with _translate_errors():
+        async with request as response:
+            if not read_body:
+                return response, b''
+
+            if max_size is None:
+                return response, await response.read()
+
+            too_large = f'The response body is larger than {max_size} bytes'
+            if (response.content_length or 0) > max_size:
+                raise ResponseTooLarge(response, too_large)
+
+            # the length may be unknown, or differ once the body is decompressed
+            body = bytearray()
+            async for chunk in response.content.iter_any():
+                body += chunk
+                if len(body) > max_size:
+                    raise ResponseTooLarge(response, too_large)
+
+            return response, bytes(body)
+
+
+def _get_ssl(options: Dict[str, Any], /) -> Union[bool, ssl.SSLContext]:
+    context = options.get('ssl', True)
+    if context is False:
+        raise ValueError(
+            'Pass danger_accept_invalid_certs=True to disable certificate verification'
+        )
+
+    # FERRIS_USE_SSL=false predates the option, so it still disables verification
+    if options.get('danger_accept_invalid_certs', not HTTPClient.USE_SSL):
+        log.warning(
+            'SSL certificate verification is disabled, never do this in production'
+        )
+        return False
+
+    if ca_data := options.get('ca_data'):
+        context = ssl.create_default_context()
+        context.load_verify_locations(cadata=ca_data)
+
+    return context
+
+
+def _get_timeout(options: Dict[str, Any], /) -> aiohttp.ClientTimeout:
+    # sock_connect covers both establishing the connection and the TLS handshake
+    return aiohttp.ClientTimeout(
+        total=options.get('timeout', HTTPClient.TIMEOUT),
+        sock_connect=options.get('connect_timeout'),
+    )
+
+
+def _get_proxy(
+    options: Dict[str, Any], /
+) -> Tuple[Optional[str], Optional[aiohttp.BasicAuth]]:
+    proxy = options.get('proxy')
+    if proxy is None:
+        return None, None
+
+    parts = urlsplit(proxy)
+    if parts.scheme not in ('http', 'https') or not parts.hostname:
+        raise ValueError(f'{proxy!r} is not a valid proxy URL')
+
+    if proxy_auth := options.get('proxy_auth'):
+        return proxy, aiohttp.BasicAuth(*proxy_auth)
+
+    return proxy, None
+
+
+def _is_transient(error: RequestError, /, *, replayable: bool = True) -> bool:
+    # certificate errors are connection errors too, but retrying them is pointless
+    if isinstance(error.original, aiohttp.ClientSSLError):
+        return False
+
+    # a request is never sent if the connection could not be established, while
+    # after a timeout or disconnect the server may already have processed it
+    if isinstance(error.original, aiohttp.ClientConnectorError):
+        return True
+
+    return replayable and isinstance(error, (RequestTimeout, ConnectionFailure))
+
+
+def _remove_quietly(path: str, /) -> None:
+    with suppress(FileNotFoundError):
+        os.remove(path)
+
+
+def _get_backoff(base: float, tries: int, /, jitter: bool = False) -> float:
+    backoff = base * 2 ** tries
+    if jitter:
+        return random.uniform(0, backoff)
+
+    return backoff
+
+
+def _parse_retry_after(response: aiohttp.ClientResponse, /) -> Optional[float]:
+    # Retry-After is either a number of seconds or an HTTP date
+    value = response.headers.get('Retry-After')
+    if value is None:
+        return None
+
+    try:
+        return max(float(value), 0)
+    except ValueError:
+        pass
+
+    try:
+        retry_at = parsedate_to_datetime(value)
+    except (TypeError, ValueError):
+        return None
+
+    if retry_at.tzinfo is None:
+        retry_at = retry_at.replace(tzinfo=timezone.utc)
+
+    return max((retry_at - datetime.now(timezone.utc)).total_seconds(), 0)
+
+
+def _describe_body(
+    response: aiohttp.ClientResponse, body: bytes, encoding: str, /
+) -> str:
+    # binary bodies such as assets are summarized instead of being dumped
+    content_type = response.content_type
+    if not (content_type.startswith('text/') or content_type.endswith('json')):
+        return f'{len(body)} bytes of {content_type}'
+
+    return _decode_leniently(body, encoding)
+
+
+def _decode_leniently(body: bytes, encoding: str, /) -> str:
+    # for error bodies and logs, where failing to decode must not hide the response
+    try:
+        return body.decode(encoding, 'replace')
+    except LookupError:
+        return body.decode('utf-8', 'replace')
+
+
+def _get_error(response: aiohttp.ClientResponse, content: str, /) -> HTTPException:
+    if response.status == 400:
+        # HEAD responses and some errors have no body at all
+        data = from_json(content) or {}
+        reason = data.get('reason')
+        location = data.get('location')
+        if location:
+            line = location.get('line')
+            character = location.get('character')
+        else:
+            line = character = None
+        return BadRequest(
+            response,
+            f'{reason}\nLine: {line} Character: {character}',
+            body=content,
+        )
+
+    if response.status == 404:
+        return NotFound(response, content, body=content)
+
+    if response.status == 401:
+        return Unauthorized(response, content, body=content)
+
+    if response.status == 403:
+        return Forbidden(response, content, body=content)
+
+    if response.status == 500:
+        try:
+            data = from_json(content) or {}
+            reason = data.get('reason')
+        except:  # TODO: Fix broad except
+            reason = content
+
+        return FerrisServerError(response, reason, body=content)
+
+    if response.status == 501:
+        return MissingImplementation(response, content, body=content)
+
+    if 500 <= response.status < 600:
+        return FerrisUnavailable(response, content, body=content)
+
+    return HTTPException(response, content, body=content)
+
+
+def _get_conditional_headers(response: HTTPResponse, /) -> Dict[str, str]:
+    headers = {}
+    if (etag := response.headers.get('ETag')) is not None:
+        headers['If-None-Match'] = etag
+
+    if (last_modified := response.headers.get('Last-Modified')) is not None:
+        headers['If-Modified-Since'] = last_modified
+
+    return headers
+
+
+def _validate_headers(headers: Dict[str, SupportsStr], /) -> Dict[str, str]:
+    validated = {}
+
+    for name, value in headers.items():
+        if not TOKEN_REGEX.fullmatch(name):
+            raise ValueError(f'{name!r} is not a valid header name')
+
+        value = str(value)
+        if '\r' in value or '\n' in value:
+            raise ValueError(f'Header {name!r} has an invalid value')
+
+        validated[name] = value
+
+    return validated
+
+
+class _RateLimiter:
+    __slots__ = ('__interval', '__lock', '__next_request')
+
+    def __init__(self, requests_per_second: float, /) -> None:
+        self.__interval: float = 1 / requests_per_second
+        self.__lock: asyncio.Lock = asyncio.Lock()
+        self.__next_request: float = 0
+
+    async def acquire(self, /) -> None:
+        async with self.__lock:
+            now = time.monotonic()
+            if self.__next_request > now:
+                await asyncio.sleep(self.__next_request - now)
+                now = self.__next_request
+
+            self.__next_request = now + self.__interval
+
+
+class _CircuitBreaker:
+    __slots__ = ('__threshold', '__cooldown', '__failures', '__opened_at', '__probing')
+
+    def __init__(self, threshold: int, cooldown: float, /) -> None:
+        self.__threshold: int = threshold
+        self.__cooldown: float = cooldown
+
+        self.__failures: Dict[str, int] = {}
+        self.__opened_at: Dict[str, float] = {}
+        self.__probing: Set[str] = set()
+
+    def acquire(self, host: str, /) -> None:
+        opened_at = self.__opened_at.get(host)
+        if opened_at is None:
+            return
+
+        # once the cooldown is over, a single request is let through to probe the host
+        if host in self.__probing or time.monotonic() - opened_at < self.__cooldown:
+            raise CircuitBreakerOpen(host)
+
+        self.__probing.add(host)
+
+    def release(self, host: str, /, *, failed: Optional[bool]) -> None:
+        # None means the request neither reached nor failed to reach the host
+        self.__probing.discard(host)
+        if failed is None:
+            return
+
+        if not failed:
+            self.__failures.pop(host, None)
+            self.__opened_at.pop(host, None)
+            return
+
+        self.__failures[host] = failures = self.__failures.get(host, 0) + 1
+        if failures >= self.__threshold:
+            self.__opened_at[host] = time.monotonic()
+
+
+class _ResponseCache:
+    __slots__ = ('__max_size', '__responses')
+
+    def __init__(self, max_size: int, /) -> None:
+        self.__max_size: int = max_size
+        self.__responses: OrderedDict[Tuple[str, Optional[str]], HTTPResponse] = (
+            OrderedDict()
+        )
+
+    def get(self, key: Tuple[str, Optional[str]], /) -> Optional[HTTPResponse]:
+        response = self.__responses.get(key)
+        if response is not None:
+            self.__responses.move_to_end(key)
+
+        return response
+
+    def put(self, key: Tuple[str, Optional[str]], response: HTTPResponse, /) -> None:
+        self.__responses[key] = response
+        self.__responses.move_to_end(key)
+
+        # the least recently used response is evicted first
+        if len(self.__responses) > self.__max_size:
+            self.__responses.popitem(last=False)
+
+
+class HTTPResponse:
+    """Represents a response from FerrisChat's REST API.
+
+    Attributes
+    ----------
+    status: int
+        The status code of the response.
+    url: str
+        The URL the response came from, after following any redirects.
+    headers: :class:`multidict.CIMultiDictProxy`
+        The headers of the response. Headers sent multiple times can be
+        retrieved with ``headers.getall(name)``.
+    body: bytes
+        The raw body of the response.
+    elapsed: float
+        The number of seconds it took to get this response, across all attempts.
+    attempts: int
+        The number of times the request was sent before getting this response.
+    encoding: str
+        The charset used to decode the body of the response. This is the one
+        passed with the request, else the one declared by the response,
+        or UTF-8 if it does not declare any.
+    links: :class:`multidict.MultiDictProxy`
+        The links from the ``Link`` header of the response, keyed by their ``rel``.
+        The target of each link is stored under ``url``, resolved against :attr:`url`.
+    cached: bool
+        Whether this is a cached response, returned because the REST API
+        responded with ``304 Not Modified``.
+    """
+
+    __slots__ = (
+        'status',
+        'url',
+        'headers',
+        'body',
+        'elapsed',
+        'attempts',
+        'encoding',
+        'links',
+        'cached',
+    )
+
+    def __init__(
+        self,
+        resp: aiohttp.ClientResponse,
+        body: bytes,
+        /,
+        *,
+        elapsed: float = 0,
+        attempts: int = 1,
+        encoding: Optional[str] = None,
+    ) -> None:
+        self.status: int = resp.status
+        self.url: str = str(resp.url)
+        self.headers: CIMultiDictProxy[str] = resp.headers
+        self.body: bytes = body
+        self.elapsed: float = elapsed
+        self.attempts: int = attempts
+        self.encoding: str = encoding or resp.charset or 'utf-8'
+        self.links: MultiDictProxy[MultiDictProxy[Any]] = resp.links
+        self.cached: bool = False
+
+    @property
+    def content(self) -> str:
+        """The body of the response, decoded with :attr:`encoding`.
+        Raises :exc:`LookupError` if the charset is unknown,
+        or :exc:`UnicodeDecodeError` if the body is not valid in it.
+        """
+        return self.body.decode(self.encoding)
+
+    @property
+    def ok(self) -> bool:
+        """Whether the status code of the response is a 2xx one."""
+        return 300 > self.status >= 200
+
+    @property
+    def location(self) -> Optional[str]:
+        """The ``Location`` header of the response, set on redirects."""
+        return self.headers.get('Location')
+
+    def json(self) -> Optional[Data]:
+        return from_json(self.content)
+
+    def __repr__(self) -> str:
+        return f'<HTTPResponse status={self.status} url={self.url!r}>'
+
+
 class APIRouter:
     __slots__ = ('__current_route', '__http_client')
 
@@ -41,7 +479,7 @@ class APIRouter:
 
     @property
     def url(self, /) -> str:
-        return HTTPClient.API_BASE_URL + self.__current_route
+        return self.__http_client.base_url + self.__current_route
 
     def _make_new(self, route: str, /) -> APIRouter:
         return self.__class__(self.__http_client, route)
@@ -72,22 +510,117 @@ class APIRouter:
 
 
 class HTTPClient:
+    """Sends requests to FerrisChat's REST API.
+
+    A client is bound to the event loop it was created in and is not thread-safe.
+    It can be shared by any number of tasks running on that loop, but other
+    threads should submit requests to it with :func:`asyncio.run_coroutine_threadsafe`
+    instead of calling it directly.
+    """
+
     API_BASE_URL: ClassVar[str] = 'https://api.ferris.chat/v0'
 
     USE_SSL: ClassVar[bool] = os.getenv('FERRIS_USE_SSL', 'true').lower() == 'true'
 
     MAX_TRIES: ClassVar[int] = 3
+    TIMEOUT: ClassVar[Optional[float]] = 300
+    BACKOFF: ClassVar[float] = 0.5
     USER_AGENT: ClassVar[
         str
     ] = f'FerrisWheel (https://github.com/FerrisChat/ferriswheel v{__version__})'
 
-    __slots__ = ('__token', '__session', '_buckets_lock', '_api_router')
+    __slots__ = (
+        '__token',
+        '__session',
+        '_buckets_lock',
+        '_api_router',
+        '_max_tries',
+        '_backoff',
+        '_jitter',
+        '_proxy',
+        '_proxy_auth',
+        '_rate_limiter',
+        '_follow_redirects',
+        '_max_redirects',
+        '_retry_statuses',
+        '_base_url',
+        '_options',
+        '_max_response_size',
+        '_circuit_breaker',
+        '_idempotency_keys',
+        '_etag_cache',
+    )
 
-    def __init__(self, token: str, /) -> None:
+    def __init__(self, token: str, /, **options) -> None:
         self.__token: str = token
+        self._options: Dict[str, Any] = options
+        self._base_url: str = options.get('base_url', self.API_BASE_URL).rstrip('/')
+        self.max_tries = options.get('max_tries', self.MAX_TRIES)
+        self._backoff: float = options.get('backoff', self.BACKOFF)
+        self._jitter: bool = options.get('jitter', False)
+        self._proxy, self._proxy_auth = _get_proxy(options)
+        self._follow_redirects: bool = options.get('follow_redirects', True)
+        self._max_redirects: int = options.get('max_redirects', 10)
+        self._max_response_size: Optional[int] = options.get('max_response_size')
+        self._idempotency_keys: bool = options.get('idempotency_keys', False)
+        self._etag_cache: Optional[_ResponseCache] = None
+        if options.get('etag_cache', False):
+            etag_cache_size = options.get('etag_cache_size', 256)
+            if etag_cache_size < 1:
+                raise ValueError('etag_cache_size must be at least 1')
+
+            self._etag_cache = _ResponseCache(etag_cache_size)
+
+        self._retry_statuses: Optional[FrozenSet[int]] = None
+        if (retry_statuses := options.get('retry_statuses')) is not None:
+            self._retry_statuses = frozenset(retry_statuses)
+
+        self._rate_limiter: Optional[_RateLimiter] = None
+        if (requests_per_second := options.get('requests_per_second')) is not None:
+            if requests_per_second <= 0:
+                raise ValueError('requests_per_second must be greater than 0')
+
+            self._rate_limiter = _RateLimiter(requests_per_second)
+
+        self._circuit_breaker: Optional[_CircuitBreaker] = None
+        if (threshold := options.get('circuit_breaker_threshold')) is not None:
+            if threshold < 1:
+                raise ValueError('circuit_breaker_threshold must be at least 1')
+
+            self._circuit_breaker = _CircuitBreaker(
+                threshold, options.get('circuit_breaker_cooldown', 30)
+            )
+
+        headers = _validate_headers(
+            {
+                'User-Agent': options.get('user_agent') or self.USER_AGENT,
+                'Authorization': self.__token,
+            }
+        )
+
+        local_addr = None
+        if (local_address := options.get('local_address')) is not None:
+            local_addr = (str(ipaddress.ip_address(local_address)), 0)
+
+        # a custom connector replaces the transport entirely, e.g. with a mock in tests
+        connector: Optional[aiohttp.BaseConnector] = options.get('connector')
+        if connector is None:
+            connector = aiohttp.TCPConnector(
+                ssl=_get_ssl(options),
+                limit=options.get('connection_limit', 100),
+                limit_per_host=options.get('connection_limit_per_host', 0),
+                keepalive_timeout=options.get('keepalive_timeout', 15),
+                local_addr=local_addr,
+            )
+
+        cookie_jar = None if options.get('cookies', True) else aiohttp.DummyCookieJar()
         self.__session: aiohttp.ClientSession = aiohttp.ClientSession(
-            headers={'User-Agent': self.USER_AGENT, 'Authorization': self.__token},
-            connector=aiohttp.TCPConnector(ssl=self.USE_SSL),
+            headers=headers,
+            connector=connector,
+            timeout=_get_timeout(options),
+            cookie_jar=cookie_jar,
+            auto_decompress=options.get('auto_decompress', True),
+            trace_configs=options.get('trace_configs'),
         )
 
         self._buckets_lock: Dict[str, asyncio.Event] = {}
@@ -97,6 +630,10 @@ class HTTPClient:
     def token(self) -> str:
         return self.__token
 
+    @property
+    def base_url(self) -> str:
+        return self._base_url
+
     @property
     def api(self) -> APIRouter:
         return self._api_router
@@ -105,31 +642,108 @@ class HTTPClient:
     def session(self) -> aiohttp.ClientSession:
         return self.__session
 
-    async def get_asset(self, url: str) -> bytes:
-        async with self.__session.get() as resp:
-            if 400 > resp.status >= 200:
-                return await resp.read()
+    @property
+    def cookie_jar(self) -> aiohttp.abc.AbstractCookieJar:
+        """The cookies stored by this client. Use ``filter_cookies(url)``
+        to read the cookies sent to a URL, and ``update_cookies(...)`` to set them.
+        """
+        return self.__session.cookie_jar
 
-            raise HTTPException(resp, 'Failed to get asset')
+    @property
+    def proxy(self) -> Optional[str]:
+        return self._proxy
+
+    @property
+    def proxy_auth(self) -> Optional[aiohttp.BasicAuth]:
+        return self._proxy_auth
+
+    @property
+    def max_tries(self) -> int:
+        return self._max_tries
+
+    @max_tries.setter
+    def max_tries(self, value: int) -> None:
+        if value < 1:
+            raise ValueError('max_tries must be at least 1')
+
+        self._max_tries: int = value
+
+    @property
+    def is_closed(self) -> bool:
+        return self.__session.closed
+
+    async def close(self) -> None:
+        """Closes the underlying session and every pooled connection.
+        The client can not be used to make requests after this.
+        """
+        await self.__session.close()
+
+    async def __aenter__(self) -> HTTPClient:
+        return self
+
+    async def __aexit__(self, *_) -> None:
+        await self.close()
+
+    def _is_retryable(self, status: int, /) -> bool:
+        if self._retry_statuses is not None:
+            return status in self._retry_statuses
+
+        return status == 429 or 500 <= status < 600
+
+    def _resolve_url(self, url: str, /) -> str:
+        if urlsplit(url).scheme:
+            return url
+
+        return f'{self._base_url}/{url.lstrip("/")}'
+
+    def _ensure_open(self) -> None:
+        if self.__session.closed:
+            raise RuntimeError('This HTTPClient is closed')
+
+    def get_asset(self, url: str) -> Awaitable[bytes]:
+        return self.request_bytes(url)
 
     @classmethod
     async def from_email_and_password(
-        cls, email: str, password, bot: bool
+        cls, email: str, password, bot: bool, **options
     ) -> HTTPClient:
         log.info('Retriving token from email and password')
-        for tries in range(cls.MAX_TRIES):
-            async with aiohttp.request(
-                'POST',
-                f'{cls.API_BASE_URL}/auth',
-                json={'email': email, 'password': password},
-                connector=aiohttp.TCPConnector(ssl=cls.USE_SSL),
-            ) as response:
-                content = await response.text('utf-8')
+        max_tries = options.get('max_tries', cls.MAX_TRIES)
+        if max_tries < 1:
+            raise ValueError('max_tries must be at least 1')
+
+        base_url = options.get('base_url', cls.API_BASE_URL).rstrip('/')
+        headers = _validate_headers(
+            {'User-Agent': options.get('user_agent') or cls.USER_AGENT}
+        )
+        backoff = options.get('backoff', cls.BACKOFF)
+        jitter = options.get('jitter', False)
+        proxy, proxy_auth = _get_proxy(options)
+
+        # an injected connector is shared with the returned client, so it is kept open
+        connector: Optional[aiohttp.BaseConnector] = options.get('connector')
+        async with aiohttp.ClientSession(
+            connector=connector or aiohttp.TCPConnector(ssl=_get_ssl(options)),
+            connector_owner=connector is None,
+            timeout=_get_timeout(options),
+            trace_configs=options.get('trace_configs'),
+        ) as session:
+            for tries in range(max_tries):
+                response, body = await _read_response(
+                    session.post(
+                        f'{base_url}/auth',
+                        json={'email': email, 'password': password},
+                        headers=headers,
+                        proxy=proxy,
+                        proxy_auth=proxy_auth,
+                    )
+                )
+                content = body.decode('utf-8', 'replace')
 
                 if 400 > response.status >= 200:
                     token = from_json(content)['token']
                     log.info('Successfully Retrived token')
-                    return cls(token)
+                    return cls(token, **options)
 
                 if response.status == 400:
                     data = from_json(content)
@@ -142,107 +756,514 @@ class HTTPClient:
                         line = character = None
 
                     raise BadRequest(
-                        response, f'{reason}\nLine: {line} Character: {character}'
+                        response,
+                        f'{reason}\nLine: {line} Character: {character}',
+                        body=content,
                     )
 
                 if response.status == 404:
-                    raise NotFound(response, content)
+                    raise NotFound(response, content, body=content)
 
                 if response.status == 401:
-                    raise Unauthorized(response, content)
+                    raise Unauthorized(response, content, body=content)
 
                 if response.status == 403:
-                    raise Forbidden(response, content)
+                    raise Forbidden(response, content, body=content)
 
                 if 500 <= response.status < 600:
-                    if tries == 1:
+                    if tries == max_tries - 1:
                         try:
                             data = from_json(content)
                             reason = data.get('reason')
                         except:  # TODO: Fix broad except
                             reason = content
 
-                        raise FerrisUnavailable(response, reason)
+                        raise FerrisUnavailable(response, reason, body=content)
 
+                    await asyncio.sleep(_get_backoff(backoff, tries, jitter))
                     continue
 
-        raise HTTPException(response, content)
+                raise HTTPException(response, content, body=content)
+
+            raise HTTPException(response, content, body=content)
 
     async def request(self, url: str, method: str, /, **kwargs) -> Optional[Data]:
+        """Sends a request and returns its decoded JSON body.
+        URLs without a scheme are treated as paths relative to :attr:`base_url`.
+
+        Keyword arguments are passed to :meth:`aiohttp.ClientSession.request`,
+        except for the following:
+
+        headers: Dict[str, str]
+            Additional headers to send with this request.
+        data: Union[str, bytes, AsyncIterable[bytes]]
+            The body of this request. A :class:`str` is assumed to be JSON, while
+            an async iterable of chunks is streamed and never retried.
+        token: str
+            The token to authenticate this request with instead of the client's.
+        charset: str
+            The charset to decode the response with, overriding the one it declares.
+        idempotency_key: str
+            The ``Idempotency-Key`` header to send with this request, overriding
+            the one generated for ``POST`` and ``PATCH`` requests.
+        timeout: float
+            The maximum number of seconds this request may take,
+            overriding the client's timeout.
+        """
+        response = await self.request_raw(url, method, **kwargs)
+        if response.status >= 300:
+            return None
+
+        return response.json()
+
+    async def request_raw(self, url: str, method: str, /, **kwargs) -> HTTPResponse:
+        """Like :meth:`request`, but returns the whole :class:`HTTPResponse`."""
+        url, method, headers, charset = self._prepare_request(url, method, kwargs)
+
+        with self._guard_host(url):
+            return await self._request(url, method, headers, charset=charset, **kwargs)
+
+    def _prepare_request(
+        self, url: str, method: str, kwargs: Dict[str, Any], /
+    ) -> Tuple[str, str, Dict[str, str], Optional[str]]:
+        # pops the options handled here from kwargs, leaving those for aiohttp
+        self._ensure_open()
+        url = self._resolve_url(url)
+
+        if not TOKEN_REGEX.fullmatch(method):
+            raise ValueError(f'{method!r} is not a valid HTTP method')
+
+        method = method.upper()
+        headers = kwargs.pop('headers', None) or {}
+        if (token := kwargs.pop('token', None)) is not None:
+            headers = {**headers, 'Authorization': token}
+
+        # generated once here so that every retry of this request shares the key
+        idempotency_key = kwargs.pop('idempotency_key', None)
+        if idempotency_key is None and self._idempotency_keys:
+            if method in ('POST', 'PATCH'):
+                idempotency_key = str(uuid.uuid4())
+
+        if idempotency_key is not None:
+            headers = {**headers, 'Idempotency-Key': idempotency_key}
+
+        headers = _validate_headers(headers)
+
+        if (charset := kwargs.pop('charset', None)) is not None:
+            charset = codecs.lookup(charset).name
+
+        if isinstance(kwargs.get('data'), str):
+            headers.setdefault('Content-Type', 'application/json')
+
+        # passing None keeps the client's timeout instead of disabling it
+        if 'timeout' in kwargs and kwargs['timeout'] is None:
+            del kwargs['timeout']
+
+        if isinstance(timeout := kwargs.get('timeout'), (int, float)):
+            # only the total is overridden, so connect_timeout still applies
+            default = self.__session.timeout
+            kwargs['timeout'] = aiohttp.ClientTimeout(
+                total=timeout,
+                connect=default.connect,
+                sock_read=default.sock_read,
+                sock_connect=default.sock_connect,
+            )
+
+        kwargs.setdefault('allow_redirects', self._follow_redirects)
+        kwargs.setdefault('max_redirects', self._max_redirects)
+
+        return url, method, headers, charset
+
+    @contextmanager
+    def _guard_host(self, url: str, /) -> Iterator[None]:
+        if self._circuit_breaker is None:
+            yield
+            return
+
+        host = urlsplit(url).netloc
+        self._circuit_breaker.acquire(host)
+
+        # only transport errors and server errors count against the host, so
+        # e.g. a cancellation or a response that fails to decode does not
+        failed: Optional[bool] = None
+        try:
+            yield
+            failed = False
+        except RequestError:
+            failed = True
+            raise
+        except HTTPException as exc:
+            failed = exc.status >= 500
+            raise
+        finally:
+            self._circuit_breaker.release(host, failed=failed)
+
+    async def _request(
+        self,
+        url: str,
+        method: str,
+        headers: Dict[str, str],
+        /,
+        *,
+        charset: Optional[str] = None,
+        **kwargs,
+    ) -> HTTPResponse:
         bucket_key = f'{method} {url}'
         bucket = self._buckets_lock.get(bucket_key)
         if bucket is None:
             self._buckets_lock[bucket_key] = bucket = asyncio.Event()
             bucket.set()
 
-        headers = {}
-
-        if 'data' in kwargs:
-            headers['Content-Type'] = 'application/json'
-
         if not bucket.is_set():
             await bucket.wait()
 
-        for tries in range(self.MAX_TRIES):
-            async with self.__session.request(
-                method, url, headers=headers, **kwargs
-            ) as response:
-                content = await response.text('utf-8')
+        # responses depend on the query and the token too, so requests passing
+        # params are not cached and the token is part of the key
+        cache_key = None
+        if self._etag_cache is not None and method == 'GET':
+            if kwargs.get('params') is None:
+                cache_key = (url, headers.get('Authorization'))
 
-                log.debug(f'{method} {url} Returned {response.status} with {content}')
+        cached = None
+        if cache_key is not None:
+            if (cached := self._etag_cache.get(cache_key)) is not None:
+                # headers passed explicitly take precedence over the cached validators
+                headers = {**_get_conditional_headers(cached), **headers}
 
-                if 400 > response.status >= 200:
-                    return from_json(content)
+        # a streamed body is consumed by the first attempt, so it can never be retried
+        max_tries = 1 if hasattr(kwargs.get('data'), '__aiter__') else self._max_tries
+        started = time.perf_counter()
 
-                if response.status == 429:
-                    data = from_json(content)
+        # sending a request again after a transport error must not repeat its effect
+        replayable = method in IDEMPOTENT_METHODS or any(
+            name.lower() == 'idempotency-key' for name in headers
+        )
+
+        for tries in range(max_tries):
+            if self._rate_limiter is not None:
+                await self._rate_limiter.acquire()
+
+            attempt_started = time.perf_counter()
+            try:
+                response, body = await _read_response(
+                    self.__session.request(
+                        method,
+                        url,
+                        headers=headers,
+                        proxy=self._proxy,
+                        proxy_auth=self._proxy_auth,
+                        **kwargs,
+                    ),
+                    read_body=method != 'HEAD',
+                    max_size=self._max_response_size,
+                )
+            except RequestError as exc:
+                retry = _is_transient(exc, replayable=replayable)
+                if tries == max_tries - 1 or not retry:
+                    raise
+
+                log.debug(f'{method} {url} (attempt {tries + 1}) Failed with {exc!r}')
+                await asyncio.sleep(_get_backoff(self._backoff, tries, self._jitter))
+                continue
+
+            # a successful body is only decoded when its text is read, and strictly,
+            # so binary bodies are never decoded and a wrong charset raises
+            result = HTTPResponse(
+                response,
+                body,
+                elapsed=time.perf_counter() - started,
+                attempts=tries + 1,
+                encoding=charset,
+            )
+            if log.isEnabledFor(logging.DEBUG):
+                log.debug(
+                    f'{method} {url} (attempt {tries + 1}) Returned {response.status} '
+                    f'in {time.perf_counter() - attempt_started:.3f}s with '
+                    f'{_describe_body(response, body, result.encoding)}'
+                )
+
+            if 300 > response.status >= 200:
+                if cache_key is not None:
+                    if response.status == 200 and _get_conditional_headers(result):
+                        self._etag_cache.put(cache_key, result)
+
+                return result
+
+            if response.status == 304 and cached is not None:
+                result = copy.copy(cached)
+                result.elapsed = time.perf_counter() - started
+                result.attempts = tries + 1
+                result.cached = True
+                return result
+
+            if 400 > response.status >= 300:
+                # only reached when redirects are not followed or nothing was cached
+                return result
+
+            if response.status == 429 and self._is_retryable(response.status):
+                sleep = _parse_retry_after(response)
+                if sleep is None:
+                    data = from_json(_decode_leniently(body, result.encoding)) or {}
                     sleep = data.get('retry_after', 0)
-                    log.warning(
-                        f'We have been ratelimited on {method} {url}, retrying in {sleep} seconds'
-                    )
-                    bucket.clear()
-                    await asyncio.sleep(sleep)
-                    bucket.set()
-                    continue
 
-                if response.status == 400:
-                    data = from_json(content)
-                    reason = data.get('reason')
-                    location = data.get('location')
-                    if location:
-                        line = location.get('line')
-                        character = location.get('character')
-                    else:
-                        line = character = None
-                    raise BadRequest(
-                        response, f'{reason}\nLine: {line} Character: {character}'
-                    )
+                log.warning(
+                    f'We have been ratelimited on {method} {url}, retrying in {sleep} seconds'
+                )
+                bucket.clear()
+                await asyncio.sleep(sleep)
+                bucket.set()
+                continue
 
-                if response.status == 404:
-                    raise NotFound(response, content)
+            if tries < max_tries - 1 and self._is_retryable(response.status):
+                sleep = None
+                if response.status == 503:
+                    sleep = _parse_retry_after(response)
 
-                if response.status == 401:
-                    raise Unauthorized(response, content)
+                if sleep is None:
+                    sleep = _get_backoff(self._backoff, tries, self._jitter)
 
-                if response.status == 403:
-                    raise Forbidden(response, content)
+                await asyncio.sleep(sleep)
+                continue
 
-                if 500 <= response.status < 600:
-                    if tries == 1:
-                        if response.status == 500:
-                            try:
-                                data = from_json(content)
-                                reason = data.get('reason')
-                            except:  # TODO: Fix broad except
-                                reason = content
+            # only success bodies are decoded strictly, errors keep their status
+            raise _get_error(response, _decode_leniently(body, result.encoding))
 
-                            raise FerrisServerError(response, reason)
+        content = _decode_leniently(body, result.encoding)
+        raise HTTPException(response, content, body=content)
 
-                        if response.status == 501:
-                            raise MissingImplementation(response, content)
+    async def ping(self, url: str, /, *, timeout: float = 5) -> bool:
+        """Returns whether the given URL responded without a server error.
 
-                        raise FerrisUnavailable(response, content)
+        Unlike other requests, this is only attempted once and never raises,
+        which makes it suitable for polling until the REST API is up.
+        """
+        if self.is_closed:
+            return False
 
-                    continue
+        try:
+            with _translate_errors():
+                async with self.__session.get(
+                    self._resolve_url(url),
+                    proxy=self._proxy,
+                    proxy_auth=self._proxy_auth,
+                    timeout=aiohttp.ClientTimeout(total=timeout),
+                ) as response:
+                    return response.status < 500
+        except RequestError:
+            return False
+
+    def head(self, url: str, /, **kwargs) -> Awaitable[HTTPResponse]:
+        """Sends a HEAD request, returning the status and headers of the response."""
+        return self.request_raw(url, 'HEAD', **kwargs)
+
+    def get(self, url: str, /, **kwargs) -> Awaitable[Optional[Data]]:
+        return self.request(url, 'GET', **kwargs)
+
+    def post(self, url: str, /, **kwargs) -> Awaitable[Optional[Data]]:
+        return self.request(url, 'POST', **kwargs)
+
+    def put(self, url: str, /, **kwargs) -> Awaitable[Optional[Data]]:
+        return self.request(url, 'PUT', **kwargs)
+
+    def delete(self, url: str, /, **kwargs) -> Awaitable[Optional[Data]]:
+        return self.request(url, 'DELETE', **kwargs)
+
+    def patch(self, url: str, /, **kwargs) -> Awaitable[Optional[Data]]:
+        return self.request(url, 'PATCH', **kwargs)
+
+    async def request_bytes(self, url: str, method: str = 'GET', /, **kwargs) -> bytes:
+        """Like :meth:`request`, but returns the raw body instead of decoding it."""
+        response = await self.request_raw(url, method, **kwargs)
+        return response.body
+
+    def request_blocking(self, url: str, method: str, /, **kwargs) -> Optional[Data]:
+        """Like :meth:`request`, but blocks the calling thread until it completes.
+
+        The request is sent from a new event loop by a temporary client with the
+        same options as this one, so this can not be called from a coroutine.
+
+        .. warning::
+            None of the state of this client applies to the request. It does not
+            send or store this client's cookies, is not counted by its rate
+            limiter or circuit breaker, does not use its ETag cache and opens a
+            new connection instead of reusing a pooled one.
+        """
+        self._ensure_open()
+
+        # the temporary client would take over the connector and close it when done
+        if self._options.get('connector') is not None:
+            raise RuntimeError(
+                'request_blocking can not be used with a custom connector'
+            )
+
+        async def runner() -> Optional[Data]:
+            options = {**self._options, 'max_tries': self._max_tries}
+            async with self.__class__(self.__token, **options) as http:
+                return await http.request(url, method, **kwargs)
+
+        return asyncio.run(runner())
+
+    async def request_many(
+        self, requests: Iterable[Tuple[Any, ...]], /, *, concurrency: int = 10
+    ) -> List[Union[Optional[Data], Exception]]:
+        """Sends many requests, at most ``concurrency`` of them at a time.
+
+        Each request is a ``(url, method)`` or ``(url, method, kwargs)`` tuple.
+        Results are returned in the same order as the requests, with the raised
+        exception in place of the result of every request that failed or was
+        malformed.
+        """
+        if concurrency < 1:
+            raise ValueError('concurrency must be at least 1')
+
+        semaphore = asyncio.Semaphore(concurrency)
+
+        async def send(request: Tuple[Any, ...]) -> Optional[Data]:
+            # unpacked here so a malformed request only fails its own result
+            if not 2 <= len(request) <= 3:
+                raise ValueError(
+                    f'{request!r} is not a (url, method) or (url, method, kwargs) tuple'
+                )
+
+            url, method, *rest = request
+            kwargs = rest[0] if rest else None
+            async with semaphore:
+                return await self.request(url, method, **(kwargs or {}))
+
+        return await asyncio.gather(
+            *(send(request) for request in requests), return_exceptions=True
+        )
+
+    def request_form(
+        self, url: str, method: str, fields: Dict[str, SupportsStr], /, **kwargs
+    ) -> Awaitable[Optional[Data]]:
+        """Sends the given fields as an ``application/x-www-form-urlencoded`` body."""
+        data = {name: str(value) for name, value in fields.items()}
+        return self.request(url, method, data=data, **kwargs)
+
+    def request_multipart(
+        self,
+        url: str,
+        method: str,
+        parts: Iterable[Tuple[Any, ...]],
+        /,
+        **kwargs,
+    ) -> Awaitable[Optional[Data]]:
+        """Sends the given parts as a ``multipart/form-data`` body.
+
+        Each part is a ``(name, value)`` or ``(name, value, filename)`` tuple,
+        where ``value`` is either a :class:`str` or :class:`bytes`.
+        """
+        writer = aiohttp.MultipartWriter('form-data')
+
+        for name, value, *filename in parts:
+            params = {'name': name}
+            if filename:
+                params['filename'] = filename[0]
+
+            writer.append(value).set_content_disposition('form-data', **params)
+
+        return self.request(url, method, data=writer, **kwargs)
+
+    async def request_all_pages(
+        self, url: str, method: str = 'GET', /, **kwargs
+    ) -> AsyncIterator[Optional[Data]]:
+        """Requests the given URL and yields the decoded JSON body of each page,
+        following the ``rel="next"`` link of every response until there is none.
+
+        Each page is requested with :meth:`request_raw`, so it is retried
+        and rate limited on its own. ``params`` and the body are only sent with
+        the first request, since the ``next`` links carry their own query.
+        """
+        seen: Set[str] = set()
+        next_url: Optional[str] = url
+
+        while next_url is not None:
+            response = await self.request_raw(next_url, method, **kwargs)
+            if response.status >= 300:
+                return
+
+            yield response.json()
+
+            seen.add(response.url)
+            kwargs = {
+                key: value
+                for key, value in kwargs.items()
+                if key not in ('params', 'json', 'data')
+            }
+
+            next_link = response.links.get('next')
+            next_url = None if next_link is None else str(next_link['url'])
+            if next_url in seen:
+                log.warning(f'{method} {next_url} links to a page already seen')
+                return
+
+    async def stream(
+        self, url: str, method: str = 'GET', /, *, chunk_size: int = 65536, **kwargs
+    ) -> AsyncIterator[bytes]:
+        """Requests the given URL and yields the response body in chunks
+        of at most ``chunk_size`` bytes, without buffering it in memory.
+
+        Keyword arguments are handled as by :meth:`request`, but since the body
+        is not buffered the request is never retried.
+        """
+        url, method, headers, charset = self._prepare_request(url, method, kwargs)
+
+        with self._guard_host(url):
+            if self._rate_limiter is not None:
+                await self._rate_limiter.acquire()
+
+            with _translate_errors():
+                async with self.__session.request(
+                    method,
+                    url,
+                    headers=headers,
+                    proxy=self._proxy,
+                    proxy_auth=self._proxy_auth,
+                    **kwargs,
+                ) as response:
+                    # a redirect that was not followed is not the requested body either
+                    if not 300 > response.status >= 200:
+                        body = await response.read()
+                        encoding = charset or response.charset or 'utf-8'
+                        raise _get_error(response, _decode_leniently(body, encoding))
+
+                    async for chunk in response.content.iter_chunked(chunk_size):
+                        yield chunk
+
+    async def download(
+        self, url: str, path: Union[str, os.PathLike], /, **kwargs
+    ) -> int:
+        """Streams the body of the given URL into the file at ``path``
+        and returns the number of bytes written.
+
+        The file is only created once the whole body was received,
+        so a failed download never leaves a partial file behind.
+        """
+        loop = asyncio.get_running_loop()
+        directory, name = os.path.split(os.fspath(path))
+
+        # a unique file next to the target, so no existing file is overwritten
+        # and the finished one can be moved into place atomically
+        fd, partial = await loop.run_in_executor(
+            None,
+            lambda: tempfile.mkstemp(
+                prefix=f'.{name}.', suffix='.part', dir=directory or os.curdir
+            ),
+        )
+        written = 0
+
+        try:
+            f = await loop.run_in_executor(None, os.fdopen, fd, 'wb')
+            try:
+                async for chunk in self.stream(url, 'GET', **kwargs):
+                    written += await loop.run_in_executor(None, f.write, chunk)
+            finally:
+                await loop.run_in_executor(None, f.close)
+
+            await loop.run_in_executor(None, os.replace, partial, path)
+        except BaseException:
+            await loop.run_in_executor(None, _remove_quietly, partial)
+            raise
 
-        raise HTTPException(response, content)
+        return written
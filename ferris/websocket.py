@@ -168,7 +168,9 @@ class Websocket:
         if not self._ws_url:
             await self.prepare()
 
-        self.ws = await self._http.session.ws_connect(self._ws_url)
+        self.ws = await self._http.session.ws_connect(
+            self._ws_url, proxy=self._http.proxy, proxy_auth=self._http.proxy_auth
+        )
 
         await self.send(
             {'c': 'Identify', 'd': {'token': self._http.token, 'intents': 0}}
@@ -0,0 +1,576 @@
+import asyncio
+import gzip
+import os
+import socket
+import tempfile
+import time
+import unittest
+from typing import Awaitable, Callable, List
+
+import aiohttp
+from aiohttp import web
+from aiohttp.test_utils import TestServer
+
+from ferris.errors import (
+    BadRequest,
+    ConnectionFailure,
+    FerrisUnavailable,
+    NotFound,
+    RequestError,
+    RequestTimeout,
+    ResponseTooLarge,
+)
+from ferris.http import HTTPClient, _get_backoff
+
+Handler = Callable[[web.Request], Awaitable[web.StreamResponse]]
+
+
+class HTTPClientTest(unittest.IsolatedAsyncioTestCase):
+    async def asyncSetUp(self) -> None:
+        self.requests: List[web.Request] = []
+        self.handler: Handler = self.ok
+
+        app = web.Application()
+        app.router.add_route('*', '/{tail:.*}', self.dispatch)
+
+        self.server = TestServer(app)
+        await self.server.start_server()
+        self.addAsyncCleanup(self.server.close)
+
+    async def dispatch(self, request: web.Request) -> web.StreamResponse:
+        self.requests.append(request)
+        return await self.handler(request)
+
+    async def ok(self, request: web.Request) -> web.StreamResponse:
+        return web.json_response({'ok': True})
+
+    def make_client(self, **options) -> HTTPClient:
+        options = {'base_url': str(self.server.make_url('')), 'backoff': 0, **options}
+        http = HTTPClient('token', **options)
+        self.addAsyncCleanup(http.close)
+        return http
+
+    async def test_retries_up_to_max_tries(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            return web.Response(status=502)
+
+        sent = []
+
+        async def on_request_start(session, context, params) -> None:
+            sent.append((params.method, params.url.path))
+
+        trace = aiohttp.TraceConfig()
+        trace.on_request_start.append(on_request_start)
+
+        self.handler = handler
+        http = self.make_client(max_tries=3, trace_configs=[trace])
+
+        with self.assertRaises(FerrisUnavailable):
+            await http.get('/flaky')
+
+        self.assertEqual(sent, [('GET', '/flaky')] * 3)
+
+    async def test_succeeds_on_the_last_try(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            if len(self.requests) < 3:
+                return web.Response(status=502)
+
+            return web.json_response({'ok': True})
+
+        self.handler = handler
+        http = self.make_client(max_tries=3)
+
+        response = await http.request_raw('/flaky', 'GET')
+        self.assertEqual(response.json(), {'ok': True})
+        self.assertEqual(response.attempts, 3)
+
+
+    def test_max_tries_must_be_positive(self) -> None:
+        with self.assertRaises(ValueError):
+            HTTPClient('token', max_tries=0)
+
+    async def test_invalid_method(self) -> None:
+        http = self.make_client()
+
+        for method in ('GE T', 'GET\n', ''):
+            with self.assertRaises(ValueError):
+                await http.request('/resource', method)
+
+        self.assertEqual(self.requests, [])
+
+    async def test_backoff_grows_geometrically(self) -> None:
+        delays = [_get_backoff(0.5, tries) for tries in range(4)]
+        self.assertEqual(delays, [0.5, 1, 2, 4])
+
+        async def handler(request: web.Request) -> web.StreamResponse:
+            if len(self.requests) < 3:
+                return web.Response(status=502)
+
+            return web.json_response({'ok': True})
+
+        self.handler = handler
+        http = self.make_client(max_tries=3, backoff=0.1)
+
+        # two retries wait 0.1 and then 0.2 seconds
+        response = await http.request_raw('/flaky', 'GET')
+        self.assertGreaterEqual(response.elapsed, 0.3)
+
+    async def test_retry_after(self) -> None:
+        for status in (429, 503):
+            self.requests.clear()
+
+            async def handler(request: web.Request) -> web.StreamResponse:
+                if len(self.requests) == 1:
+                    return web.Response(status=status, headers={'Retry-After': '0'})
+
+                return web.json_response({'ok': True})
+
+            self.handler = handler
+            http = self.make_client(max_tries=2)
+
+            response = await http.request_raw('/limited', 'GET')
+            self.assertEqual(response.json(), {'ok': True})
+            self.assertEqual(response.attempts, 2)
+
+    async def test_form_body(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            form = await request.post()
+            return web.json_response(
+                {'content_type': request.content_type, 'count': form['count']}
+            )
+
+        self.handler = handler
+        http = self.make_client()
+
+        data = await http.request_form('/form', 'POST', {'count': 2})
+        self.assertEqual(
+            data, {'content_type': 'application/x-www-form-urlencoded', 'count': '2'}
+        )
+
+    async def test_multipart_body(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            form = await request.post()
+            _, _, boundary = request.headers['Content-Type'].partition('boundary=')
+            return web.json_response(
+                {
+                    'content_type': request.content_type,
+                    'boundary': boundary,
+                    'name': form['name'],
+                    'filename': form['file'].filename,
+                    'file': form['file'].file.read().decode(),
+                }
+            )
+
+        self.handler = handler
+        http = self.make_client()
+
+        data = await http.request_multipart(
+            '/upload', 'POST', [('name', 'value'), ('file', b'data', 'a.txt')]
+        )
+        self.assertEqual(data['content_type'], 'multipart/form-data')
+        self.assertTrue(data['boundary'])
+        self.assertEqual(data['name'], 'value')
+        self.assertEqual(data['filename'], 'a.txt')
+        self.assertEqual(data['file'], 'data')
+
+    async def test_gzip_body_is_decompressed(self) -> None:
+        compressed = gzip.compress(b'{"ok": true}')
+
+        async def handler(request: web.Request) -> web.StreamResponse:
+            return web.Response(
+                body=compressed,
+                content_type='application/json',
+                headers={'Content-Encoding': 'gzip'},
+            )
+
+        self.handler = handler
+
+        http = self.make_client()
+        self.assertEqual(await http.get('/compressed'), {'ok': True})
+
+        http = self.make_client(auto_decompress=False)
+        self.assertEqual(await http.request_bytes('/compressed'), compressed)
+
+    def test_jittered_backoff_differs(self) -> None:
+        delays = [_get_backoff(1, 3, jitter=True) for _ in range(20)]
+
+        self.assertGreater(len(set(delays)), 1)
+        self.assertTrue(all(0 <= delay <= 8 for delay in delays))
+
+    async def test_head_reads_no_body(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            return web.Response(body=b'x' * 100, content_type='text/plain')
+
+        self.handler = handler
+        http = self.make_client()
+
+        response = await http.head('/resource')
+        self.assertEqual(response.status, 200)
+        self.assertEqual(response.body, b'')
+        self.assertEqual(response.headers['Content-Length'], '100')
+
+    async def test_head_error_without_body(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            return web.Response(status=400)
+
+        self.handler = handler
+        http = self.make_client()
+
+        with self.assertRaises(BadRequest):
+            await http.head('/resource')
+
+    async def test_per_request_timeout(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            await asyncio.sleep(0.5)
+            return web.json_response({'ok': True})
+
+        self.handler = handler
+        http = self.make_client(max_tries=1, timeout=0.2)
+
+        self.assertEqual(await http.get('/slow', timeout=2), {'ok': True})
+
+        with self.assertRaises(RequestTimeout):
+            await http.get('/slow')
+
+        # None keeps the client's timeout rather than disabling it
+        with self.assertRaises(RequestTimeout):
+            await http.get('/slow', timeout=None)
+
+    async def test_timeouts_are_retried(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            if len(self.requests) == 1:
+                await asyncio.sleep(1)
+
+            return web.json_response({'ok': True})
+
+        self.handler = handler
+        http = self.make_client(max_tries=2)
+
+        response = await http.request_raw('/slow', 'GET', timeout=0.2)
+        self.assertEqual(response.json(), {'ok': True})
+        self.assertEqual(response.attempts, 2)
+
+    async def test_timed_out_post_is_not_resent(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            if len(self.requests) == 1:
+                await asyncio.sleep(1)
+
+            return web.json_response({'ok': True})
+
+        self.handler = handler
+        http = self.make_client(max_tries=2)
+
+        with self.assertRaises(RequestTimeout):
+            await http.post('/messages', timeout=0.2)
+
+        self.assertEqual(len(self.requests), 1)
+
+        # an idempotency key lets the server discard the duplicate
+        self.requests.clear()
+        response = await http.request_raw(
+            '/messages', 'POST', timeout=0.2, idempotency_key='key'
+        )
+        self.assertEqual(response.attempts, 2)
+
+    async def test_max_response_size(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            return web.Response(body=b'x' * 100)
+
+        self.handler = handler
+        http = self.make_client(max_response_size=10)
+
+        with self.assertRaises(ResponseTooLarge):
+            await http.request_bytes('/large')
+
+        self.assertEqual(len(self.requests), 1)
+
+    async def test_max_response_size_without_content_length(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            response = web.StreamResponse()
+            response.enable_chunked_encoding()
+            await response.prepare(request)
+
+            for _ in range(10):
+                await response.write(b'x' * 10)
+
+            await response.write_eof()
+            return response
+
+        self.handler = handler
+
+        http = self.make_client(max_response_size=100)
+        self.assertEqual(await http.request_bytes('/chunked'), b'x' * 100)
+
+        http = self.make_client(max_response_size=50)
+        with self.assertRaises(ResponseTooLarge):
+            await http.request_bytes('/chunked')
+
+    async def test_connect_timeout(self) -> None:
+        # accepts connections, but never answers the TLS handshake
+        async def stall(reader: asyncio.StreamReader, writer: asyncio.StreamWriter):
+            await reader.read()
+            writer.close()
+
+        server = await asyncio.start_server(stall, '127.0.0.1', 0)
+        port = server.sockets[0].getsockname()[1]
+
+        async def close_server() -> None:
+            server.close()
+            await server.wait_closed()
+
+        self.addAsyncCleanup(close_server)
+
+        http = self.make_client(
+            base_url=f'https://127.0.0.1:{port}',
+            max_tries=1,
+            timeout=30,
+            connect_timeout=0.2,
+        )
+
+        started = time.perf_counter()
+        with self.assertRaises(RequestTimeout):
+            await http.get('/', timeout=60)
+
+        self.assertLess(time.perf_counter() - started, 5)
+
+    async def test_declared_charset(self) -> None:
+        body = '{"name": "café"}'.encode('latin-1')
+
+        async def handler(request: web.Request) -> web.StreamResponse:
+            return web.Response(
+                body=body, content_type='application/json', charset='latin-1'
+            )
+
+        self.handler = handler
+        http = self.make_client()
+
+        response = await http.request_raw('/legacy', 'GET')
+        self.assertEqual(response.encoding, 'latin-1')
+        self.assertEqual(response.json(), {'name': 'café'})
+
+        # the body is not valid UTF-8, so overriding the charset must not mangle it
+        with self.assertRaises(UnicodeDecodeError):
+            await http.get('/legacy', charset='utf-8')
+
+        with self.assertRaises(LookupError):
+            await http.get('/legacy', charset='x-unknown')
+
+    async def test_unknown_declared_charset(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            return web.Response(
+                body=b'\x00\x01', content_type='image/png', charset='x-unknown'
+            )
+
+        self.handler = handler
+        http = self.make_client()
+
+        # bytes are never decoded, so an unknown charset only fails when text is read
+        self.assertEqual(await http.request_bytes('/asset'), b'\x00\x01')
+
+        response = await http.request_raw('/asset', 'GET')
+        with self.assertRaises(LookupError):
+            response.content
+
+    async def test_undecodable_error_body(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            return web.Response(
+                status=404,
+                body=b'\xff\xfe',
+                content_type='image/png',
+                charset='x-unknown',
+            )
+
+        self.handler = handler
+        http = self.make_client()
+
+        # the status is kept even though the body is not valid text
+        with self.assertRaises(NotFound) as ctx:
+            await http.request_bytes('/missing.png')
+
+        self.assertEqual(ctx.exception.status, 404)
+
+    async def test_timeout_error(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            await asyncio.sleep(1)
+            return web.json_response({'ok': True})
+
+        self.handler = handler
+        http = self.make_client(max_tries=1)
+
+        with self.assertRaises(RequestTimeout) as ctx:
+            await http.get('/slow', timeout=0.2)
+
+        self.assertIsInstance(ctx.exception, RequestError)
+
+    async def test_connection_failure(self) -> None:
+        with socket.socket() as sock:
+            sock.bind(('127.0.0.1', 0))
+            port = sock.getsockname()[1]
+
+        attempts = []
+
+        async def on_request_start(session, context, params) -> None:
+            attempts.append(params.url)
+
+        trace = aiohttp.TraceConfig()
+        trace.on_request_start.append(on_request_start)
+
+        http = self.make_client(
+            base_url=f'http://127.0.0.1:{port}', max_tries=2, trace_configs=[trace]
+        )
+
+        with self.assertRaises(ConnectionFailure) as ctx:
+            await http.get('/refused')
+
+        self.assertIsInstance(ctx.exception, RequestError)
+        self.assertNotIsInstance(ctx.exception, RequestTimeout)
+        self.assertEqual(len(attempts), 2)
+
+    async def test_etag_cache(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            if request.headers.get('If-None-Match') == '"v1"':
+                return web.Response(status=304)
+
+            return web.json_response({'version': 1}, headers={'ETag': '"v1"'})
+
+        self.handler = handler
+        http = self.make_client(etag_cache=True)
+
+        first = await http.request_raw('/resource', 'GET')
+        self.assertFalse(first.cached)
+
+        second = await http.request_raw('/resource', 'GET')
+        self.assertTrue(second.cached)
+        self.assertEqual(second.status, 200)
+        self.assertEqual(second.json(), {'version': 1})
+
+        self.assertEqual(
+            [request.headers.get('If-None-Match') for request in self.requests],
+            [None, '"v1"'],
+        )
+
+    async def test_etag_cache_skips_params(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            return web.json_response({'ok': True}, headers={'ETag': '"v1"'})
+
+        self.handler = handler
+        http = self.make_client(etag_cache=True)
+
+        await http.get('/resource', params={'page': 1})
+        await http.get('/resource', params={'page': 2})
+
+        self.assertEqual(
+            [request.headers.get('If-None-Match') for request in self.requests],
+            [None, None],
+        )
+
+    async def test_etag_cache_evicts_least_recently_used(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            return web.json_response({'ok': True}, headers={'ETag': '"v1"'})
+
+        self.handler = handler
+        http = self.make_client(etag_cache=True, etag_cache_size=1)
+
+        await http.get('/first')
+        await http.get('/second')
+        await http.get('/first')
+
+        self.assertEqual(
+            [request.headers.get('If-None-Match') for request in self.requests],
+            [None, None, None],
+        )
+
+    def test_etag_cache_size_must_be_positive(self) -> None:
+        with self.assertRaises(ValueError):
+            HTTPClient('token', etag_cache=True, etag_cache_size=0)
+
+    async def test_login_uses_injected_transport(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            if request.path == '/auth':
+                return web.json_response({'token': 'new token'})
+
+            return web.json_response({'ok': True})
+
+        sent = []
+
+        async def on_request_start(session, context, params) -> None:
+            sent.append((params.method, params.url.path))
+
+        trace = aiohttp.TraceConfig()
+        trace.on_request_start.append(on_request_start)
+
+        self.handler = handler
+        http = await HTTPClient.from_email_and_password(
+            'email',
+            'password',
+            False,
+            base_url=str(self.server.make_url('')),
+            connector=aiohttp.TCPConnector(),
+            trace_configs=[trace],
+        )
+        self.addAsyncCleanup(http.close)
+
+        # the connector is still open for the client that logged in
+        self.assertEqual(http.token, 'new token')
+        self.assertEqual(await http.get('/me'), {'ok': True})
+        self.assertEqual(sent, [('POST', '/auth'), ('GET', '/me')])
+
+    async def test_download(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            if request.path == '/missing':
+                return web.Response(status=404)
+
+            return web.Response(body=b'asset')
+
+        self.handler = handler
+        http = self.make_client()
+
+        with tempfile.TemporaryDirectory() as directory:
+            path = os.path.join(directory, 'asset.png')
+
+            # a file that happens to have the old partial name is left alone
+            with open(f'{path}.part', 'wb') as f:
+                f.write(b'unrelated')
+
+            self.assertEqual(await http.download('/asset', path), 5)
+            with open(path, 'rb') as f:
+                self.assertEqual(f.read(), b'asset')
+
+            with self.assertRaises(NotFound):
+                await http.download('/missing', os.path.join(directory, 'missing'))
+
+            self.assertEqual(
+                sorted(os.listdir(directory)), ['asset.png', 'asset.png.part']
+            )
+            with open(f'{path}.part', 'rb') as f:
+                self.assertEqual(f.read(), b'unrelated')
+
+    def test_disabling_verification_is_explicit(self) -> None:
+        with self.assertRaises(ValueError):
+            HTTPClient('token', ssl=False)
+
+    async def test_ping(self) -> None:
+        async def handler(request: web.Request) -> web.StreamResponse:
+            return web.Response(status=503 if request.path == '/down' else 200)
+
+        self.handler = handler
+        http = self.make_client()
+
+        self.assertTrue(await http.ping('/health'))
+        self.assertFalse(await http.ping('/down'))
+
+        await http.close()
+        self.assertFalse(await http.ping('/health'))
+
+    def test_requests_per_second_must_be_positive(self) -> None:
+        for requests_per_second in (0, -1):
+            with self.assertRaises(ValueError):
+                HTTPClient('token', requests_per_second=requests_per_second)
+
+    def test_circuit_breaker_threshold_must_be_positive(self) -> None:
+        with self.assertRaises(ValueError):
+            HTTPClient('token', circuit_breaker_threshold=0)
+
+
+if __name__ == '__main__':
+    unittest.main()